@@ -0,0 +1,180 @@
+#![allow(clippy::result_unit_err)]
+
+use std::{io::Read, path::PathBuf, sync::Mutex};
+
+use ext2::{Ext2File, Ext2Fs};
+use ms_hostcall::types::{Fd, FileStat, OpenFlags, Size};
+pub use ms_std;
+use ms_std::libos::libos;
+use vfs::{VfsBackend, VfsFile};
+
+// ext2 images are mounted read-only: this backend targets distro-style
+// rootfs images that FAT can't represent (symlinks, permissions, large
+// directory trees), not scratch space for a running isolation.
+fn get_fs_image_path() -> PathBuf {
+    let image_path = match libos!(fs_image(ms_std::init_context::isolation_ctx().isol_id)) {
+        Some(s) => s,
+        None => "fs_images/ext2.img".to_owned(),
+    };
+
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf()
+        .join(image_path)
+}
+
+thread_local! {
+    static FS_RAW: Ext2Fs = {
+        let image_path = get_fs_image_path();
+        Ext2Fs::open(&image_path)
+            .unwrap_or_else(|e| panic!("open img {:?} failed, err: {}", image_path, e))
+    };
+
+    static FTABLE: Mutex<Vec<Option<Box<dyn VfsFile>>>> = Mutex::new(Vec::default());
+}
+
+fn get_fs_ref() -> &'static Ext2Fs {
+    let fs_addr = FS_RAW.with(|fs| fs as *const _ as usize);
+    unsafe { &*(fs_addr as *const Ext2Fs) }
+}
+
+fn get_file_mut(fd: Fd) -> &'static mut dyn VfsFile {
+    FTABLE.with(|ft| {
+        let mut ft = ft.lock().expect("require lock failed.");
+        if let Some(Some(file)) = ft.get_mut(fd as usize) {
+            let file_addr = file.as_mut() as *mut dyn VfsFile;
+            unsafe { &mut *file_addr }
+        } else {
+            panic!("fd don't exist");
+        }
+    })
+}
+
+struct Ext2FileHandle(Ext2File<'static>);
+
+impl VfsFile for Ext2FileHandle {
+    fn read(&mut self, buf: &mut [u8]) -> Result<Size, ()> {
+        self.0.read(buf).map_err(|_| ())
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> Result<Size, ()> {
+        // ext2 images are mounted read-only; see the `rejects_write` guard
+        // in `Ext2Backend::open`.
+        Err(())
+    }
+
+    fn stat(&self) -> Result<FileStat, ()> {
+        let inode = self.0.inode();
+
+        Ok(FileStat {
+            size: inode.size(),
+            created: (inode.ctime() as i64, 0),
+            accessed: (inode.atime() as i64, 0),
+            modified: (inode.mtime() as i64, 0),
+        })
+    }
+}
+
+// ext2 images are mounted read-only; pulled out of `Ext2Backend::open` so
+// the rejection logic can be tested without a mounted image.
+fn rejects_write(flags: OpenFlags) -> bool {
+    flags.intersects(
+        OpenFlags::O_WRONLY
+            | OpenFlags::O_RDWR
+            | OpenFlags::O_CREAT
+            | OpenFlags::O_APPEND
+            | OpenFlags::O_TRUNC,
+    )
+}
+
+#[test]
+fn rejects_write_test() {
+    assert!(!rejects_write(OpenFlags::O_RDONLY));
+    assert!(rejects_write(OpenFlags::O_CREAT));
+    assert!(rejects_write(OpenFlags::O_WRONLY));
+    assert!(rejects_write(OpenFlags::O_WRONLY | OpenFlags::O_CREAT));
+}
+
+/// The read-only `VfsBackend` for this crate's ext2 mount, dispatched
+/// through the same `fatfs_*` hostcall names as the `fatfs` crate's
+/// backend so an isolation can pick either mount type by `fs_type`.
+struct Ext2Backend;
+
+impl VfsBackend for Ext2Backend {
+    fn open(&self, path: &str, flags: OpenFlags) -> Result<Box<dyn VfsFile>, ()> {
+        if rejects_write(flags) {
+            return Err(());
+        }
+
+        let file = get_fs_ref().open_file(path).map_err(|_| ())?;
+        Ok(Box::new(Ext2FileHandle(file)))
+    }
+
+    fn readdir(&self, path: &str) -> Result<Vec<String>, ()> {
+        get_fs_ref()
+            .read_dir(path)
+            .map_err(|_| ())?
+            .map(|e| e.map(|e| e.name().to_owned()).map_err(|_| ()))
+            .collect()
+    }
+}
+
+#[no_mangle]
+pub fn fatfs_open(p: &str, flags: OpenFlags) -> Result<Fd, ()> {
+    let file = Ext2Backend.open(p, flags)?;
+
+    let fd = FTABLE.with(|table| {
+        let mut table = table.lock().expect("require lock failed.");
+        table.push(Some(file));
+        table.len() - 1
+    });
+
+    Ok(fd as u32)
+}
+
+#[no_mangle]
+pub fn fatfs_readdir(p: &str) -> Result<Vec<String>, ()> {
+    Ext2Backend.readdir(p)
+}
+
+#[no_mangle]
+pub fn fatfs_read(fd: Fd, buf: &mut [u8]) -> Result<Size, ()> {
+    get_file_mut(fd).read(buf)
+}
+
+#[no_mangle]
+pub fn fatfs_write(fd: Fd, buf: &[u8]) -> Result<Size, ()> {
+    get_file_mut(fd).write(buf)
+}
+
+#[no_mangle]
+pub fn fatfs_close(fd: Fd) -> Result<(), ()> {
+    let mut old_file = None;
+
+    FTABLE.with(|ftable| {
+        let mut ftable = ftable.lock().expect("require lock failed.");
+        if (fd as usize) < ftable.len() {
+            std::mem::swap(&mut ftable[fd as usize], &mut old_file)
+        };
+    });
+
+    if let Some(file) = old_file {
+        file.close()
+    } else {
+        Err(())
+    }
+}
+
+#[no_mangle]
+pub fn fatfs_stat(fd: Fd) -> Result<FileStat, ()> {
+    FTABLE.with(|ft| {
+        let ft = ft.lock().expect("require lock failed.");
+        ft.get(fd as usize)
+            .and_then(|slot| slot.as_ref())
+            .ok_or(())?
+            .stat()
+    })
+}
@@ -8,9 +8,10 @@ use std::{
 };
 
 use fscommon::BufStream;
-use ms_hostcall::types::{Fd, OpenFlags, Size};
+use ms_hostcall::types::{Fd, FileStat, OpenFlags, Size, Timespec};
 pub use ms_std;
 use ms_std::libos::libos;
+use vfs::{VfsBackend, VfsFile};
 
 type FileSystem = fatfs::FileSystem<fscommon::BufStream<std::fs::File>>;
 type File<'a> = fatfs::File<'a, fscommon::BufStream<std::fs::File>>;
@@ -44,7 +45,9 @@ thread_local! {
         FileSystem::new(image, fatfs::FsOptions::new()).expect("fatfs::new() failed.")
     };
 
-    static FTABLE: Mutex<Vec<Option<File<'static>>>> = Mutex::new(Vec::default());
+    // Holds `Box<dyn VfsFile>` (see the `vfs` crate) rather than a concrete
+    // fatfs file type; the `ext2` crate keeps its own table of the same shape.
+    static FTABLE: Mutex<Vec<Option<Box<dyn VfsFile>>>> = Mutex::new(Vec::default());
 }
 
 fn get_fs_ref() -> &'static FileSystem {
@@ -54,35 +57,105 @@ fn get_fs_ref() -> &'static FileSystem {
     unsafe { &*(fs_addr as *const FileSystem) }
 }
 
-fn get_file_mut(fd: Fd) -> &'static mut File<'static> {
+fn get_file_mut(fd: Fd) -> &'static mut dyn VfsFile {
     FTABLE.with(|ft| {
         let mut ft = ft.lock().expect("require lock failed.");
         if let Some(Some(file)) = ft.get_mut(fd as usize) {
-            let file_addr = file as *const _ as usize;
-            // println!("get_file_mut: file addr=0x{:x}", file_addr);
-            unsafe { &mut *(file_addr as *mut File) }
+            let file_addr = file.as_mut() as *mut dyn VfsFile;
+            unsafe { &mut *file_addr }
         } else {
             panic!("fd don't exist");
         }
     })
 }
 
+struct FatfsFile {
+    file: File<'static>,
+    path: String,
+}
+
+impl VfsFile for FatfsFile {
+    fn read(&mut self, buf: &mut [u8]) -> Result<Size, ()> {
+        self.file.read(buf).map_err(|_| ())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<Size, ()> {
+        self.file.write_all(buf).map_err(|_| ())?;
+        self.file.flush().map_err(|_| ())?;
+        Ok(buf.len())
+    }
+
+    fn stat(&self) -> Result<FileStat, ()> {
+        let entry = find_entry(&self.path)?;
+
+        Ok(FileStat {
+            size: entry.len(),
+            created: datetime_to_timespec(entry.created()),
+            accessed: (date_to_epoch_days(entry.accessed()) * 86_400, 0),
+            modified: datetime_to_timespec(entry.modified()),
+        })
+    }
+}
+
+// Walk every directory component of `path` via `open_dir` before scanning
+// the final directory for the leaf name, so stat works for nested paths
+// (e.g. "dir/a.txt") and not just root-level files.
+fn find_entry(path: &str) -> Result<fatfs::DirEntry<'static, BufStream<std::fs::File>>, ()> {
+    let mut dir = get_fs_ref().root_dir();
+    let mut components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    let file_name = components.pop().ok_or(())?;
+
+    for component in components {
+        dir = dir.open_dir(component).map_err(|_| ())?;
+    }
+
+    dir.iter()
+        .find_map(|e| e.ok().filter(|e| e.file_name() == file_name))
+        .ok_or(())
+}
+
+/// The `VfsBackend` for this crate's FAT mount, so `fatfs_open`/
+/// `fatfs_readdir` dispatch through the same backend-agnostic interface
+/// other mount types (e.g. `ext2`) implement.
+struct FatfsBackend;
+
+impl VfsBackend for FatfsBackend {
+    fn open(&self, path: &str, flags: OpenFlags) -> Result<Box<dyn VfsFile>, ()> {
+        let root_dir = get_fs_ref().root_dir();
+
+        let file = if flags.contains(OpenFlags::O_CREAT) {
+            root_dir.create_file(path).map_err(|_| ())?
+        } else {
+            root_dir.open_file(path).map_err(|_| ())?
+        };
+
+        Ok(Box::new(FatfsFile {
+            file,
+            path: path.to_owned(),
+        }))
+    }
+
+    fn readdir(&self, path: &str) -> Result<Vec<String>, ()> {
+        let dir = if path.is_empty() {
+            get_fs_ref().root_dir()
+        } else {
+            get_fs_ref().root_dir().open_dir(path).map_err(|_| ())?
+        };
+
+        dir.iter()
+            .map(|e| e.map(|e| e.file_name()).map_err(|_| ()))
+            .collect()
+    }
+}
+
 #[no_mangle]
 pub fn fatfs_read(fd: Fd, buf: &mut [u8]) -> Result<Size, ()> {
-    let file = get_file_mut(fd);
-
-    Ok(file.read(buf).expect("fatfs_read failed."))
+    get_file_mut(fd).read(buf)
 }
 
 #[no_mangle]
 pub fn fatfs_open(p: &str, flags: OpenFlags) -> Result<Fd, ()> {
-    let root_dir = get_fs_ref().root_dir();
-
-    let file = if flags.contains(OpenFlags::O_CREAT) {
-        root_dir.create_file(p).expect("create file failed.")
-    } else {
-        root_dir.open_file(p).expect("open file failed.")
-    };
+    let file = FatfsBackend.open(p, flags).expect("open file failed.");
 
     let fd = FTABLE.with(|table| {
         let mut table = table.lock().expect("require lock failed.");
@@ -93,27 +166,48 @@ pub fn fatfs_open(p: &str, flags: OpenFlags) -> Result<Fd, ()> {
     Ok(fd as u32)
 }
 
+#[no_mangle]
+pub fn fatfs_readdir(p: &str) -> Result<Vec<String>, ()> {
+    FatfsBackend.readdir(p)
+}
+
 #[test]
 fn fatfs_open_test() {
-    let fd = fatfs_open("new_file.txt", OpenFlags::O_CREAT).expect("open file failed") as usize;
-    FTABLE.with(|t| {
-        let mut t = t.lock().expect("require lock failed");
-        assert!(t.len() == fd + 1);
-        if let Some(Some(ref mut f)) = t.get_mut(fd) {
-            let mut buf = String::new();
-            f.read_to_string(&mut buf).expect("read failed");
-            // assert!(!buf.is_empty());
-        };
-    })
+    let fd = fatfs_open("new_file.txt", OpenFlags::O_CREAT).expect("open file failed");
+
+    let mut buf = [0u8; 64];
+    let n = get_file_mut(fd).read(&mut buf).expect("read failed");
+    assert!(n <= buf.len());
+}
+
+#[test]
+fn fatfs_readdir_test() {
+    fatfs_open("readdir_probe.txt", OpenFlags::O_CREAT).expect("open file failed");
+
+    let entries = fatfs_readdir("").expect("readdir failed");
+    assert!(entries.iter().any(|name| name == "readdir_probe.txt"));
+}
+
+#[test]
+fn fatfs_stat_nested_path_test() {
+    let root_dir = get_fs_ref().root_dir();
+    root_dir
+        .create_dir("sub")
+        .or_else(|_| root_dir.open_dir("sub"))
+        .expect("create dir failed");
+    let sub_dir = root_dir.open_dir("sub").expect("open dir failed");
+    sub_dir
+        .create_file("nested.txt")
+        .expect("create nested file failed");
+
+    let fd = fatfs_open("sub/nested.txt", OpenFlags::O_RDONLY).expect("open file failed");
+    let stat = fatfs_stat(fd).expect("stat failed");
+    assert_eq!(stat.size, 0);
 }
 
 #[no_mangle]
 pub fn fatfs_write(fd: Fd, buf: &[u8]) -> Result<Size, ()> {
-    let file = get_file_mut(fd);
-    file.write_all(buf).expect("write file failed");
-    file.flush().expect("flush failed");
-
-    Ok(buf.len())
+    get_file_mut(fd).write(buf)
 }
 
 #[test]
@@ -138,9 +232,46 @@ pub fn fatfs_close(fd: Fd) -> Result<(), ()> {
     });
 
     if let Some(file) = old_file {
-        drop(file);
-        Ok(())
+        file.close()
     } else {
         Err(())
     }
 }
+
+#[no_mangle]
+pub fn fatfs_stat(fd: Fd) -> Result<FileStat, ()> {
+    FTABLE.with(|ft| {
+        let ft = ft.lock().expect("require lock failed.");
+        ft.get(fd as usize)
+            .and_then(|slot| slot.as_ref())
+            .ok_or(())?
+            .stat()
+    })
+}
+
+// FAT stores modification/creation times as local-calendar
+// year/month/day + hour/min/sec (access times as the date alone), not as
+// a Unix timestamp, so every
+// `fatfs_stat` call needs to fold the calendar date back into epoch days.
+// This is the usual days-from-civil algorithm (Howard Hinnant's).
+fn date_to_epoch_days(date: fatfs::Date) -> i64 {
+    let y = if date.month <= 2 {
+        date.year as i64 - 1
+    } else {
+        date.year as i64
+    };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (date.month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + date.day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+fn datetime_to_timespec(dt: fatfs::DateTime) -> Timespec {
+    let secs_of_day =
+        dt.time.hour as i64 * 3600 + dt.time.min as i64 * 60 + dt.time.sec as i64;
+
+    (date_to_epoch_days(dt.date) * 86_400 + secs_of_day, 0)
+}
@@ -0,0 +1,259 @@
+#![allow(clippy::result_unit_err)]
+
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ms_hostcall::types::{Fd, FileStat, OpenFlags, Size, Timespec};
+pub use ms_std;
+use ms_std::libos::libos;
+use vfs::VfsFile;
+
+fn get_shared_dir() -> (PathBuf, bool) {
+    let isol_id = ms_std::init_context::isolation_ctx().isol_id;
+    let (dir, writable): (String, bool) =
+        libos!(shared_dir(isol_id)).expect("isolation has no shared_dir configured.");
+
+    let root = fs::canonicalize(&dir)
+        .unwrap_or_else(|e| panic!("canonicalize shared_dir {:?} failed, err: {}", dir, e));
+
+    (root, writable)
+}
+
+thread_local! {
+    static SHARED_DIR: (PathBuf, bool) = get_shared_dir();
+
+    static FTABLE: Mutex<Vec<Option<Box<dyn VfsFile>>>> = Mutex::new(Vec::default());
+}
+
+/// Resolve a guest-relative path against `root`, rejecting any path that
+/// would escape it via `..` traversal, an absolute path, or a symlink that
+/// points outside the root. Takes `root` as a plain argument (rather than
+/// reading `SHARED_DIR` itself) so the escape checks can be tested without
+/// a running isolation.
+fn resolve_against_root(root: &Path, p: &str) -> Result<PathBuf, ()> {
+    if Path::new(p).is_absolute() {
+        return Err(());
+    }
+
+    let joined = root.join(p);
+
+    // `canonicalize` both resolves symlinks and requires every component
+    // to exist, which new files created with `O_CREAT` won't satisfy; for
+    // those, canonicalize the parent dir instead and re-attach the leaf.
+    let real = match fs::canonicalize(&joined) {
+        Ok(real) => real,
+        Err(_) => {
+            let parent = joined.parent().ok_or(())?;
+            let file_name = joined.file_name().ok_or(())?;
+            fs::canonicalize(parent).map_err(|_| ())?.join(file_name)
+        }
+    };
+
+    if real.starts_with(root) {
+        Ok(real)
+    } else {
+        Err(())
+    }
+}
+
+/// Resolve a guest-relative path against the shared root configured for
+/// this isolation. See [`resolve_against_root`] for the escape checks.
+fn resolve_guest_path(p: &str) -> Result<PathBuf, ()> {
+    let root = SHARED_DIR.with(|(root, _)| root.clone());
+    resolve_against_root(&root, p)
+}
+
+#[cfg(test)]
+mod resolve_against_root_tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    /// A fresh temp directory per test, so concurrently-run tests don't
+    /// trip over each other's files.
+    fn temp_root() -> PathBuf {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!(
+            "hostfs_resolve_test_{}_{}",
+            std::process::id(),
+            n
+        ));
+        fs::create_dir_all(&dir).expect("create temp dir failed");
+        fs::canonicalize(dir).expect("canonicalize temp dir failed")
+    }
+
+    #[test]
+    fn allows_path_inside_root() {
+        let root = temp_root();
+        fs::write(root.join("a.txt"), b"hi").expect("write failed");
+
+        let real = resolve_against_root(&root, "a.txt").expect("should resolve");
+        assert_eq!(real, root.join("a.txt"));
+    }
+
+    #[test]
+    fn rejects_dot_dot_traversal() {
+        let root = temp_root();
+        let escape_target = root.parent().unwrap().join("escaped.txt");
+        fs::write(&escape_target, b"secret").expect("write failed");
+
+        assert_eq!(resolve_against_root(&root, "../escaped.txt"), Err(()));
+
+        let _ = fs::remove_file(escape_target);
+    }
+
+    #[test]
+    fn rejects_absolute_path() {
+        let root = temp_root();
+        assert_eq!(resolve_against_root(&root, "/etc/passwd"), Err(()));
+    }
+
+    #[test]
+    fn rejects_symlink_escape() {
+        let root = temp_root();
+        let outside = std::env::temp_dir().join(format!(
+            "hostfs_resolve_test_outside_{}_{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&outside).expect("create outside dir failed");
+        fs::write(outside.join("secret.txt"), b"secret").expect("write failed");
+
+        let link = root.join("escape_link");
+        std::os::unix::fs::symlink(&outside, &link).expect("symlink failed");
+
+        assert_eq!(resolve_against_root(&root, "escape_link/secret.txt"), Err(()));
+
+        let _ = fs::remove_dir_all(outside);
+    }
+}
+
+struct HostFile(fs::File);
+
+impl VfsFile for HostFile {
+    fn read(&mut self, buf: &mut [u8]) -> Result<Size, ()> {
+        self.0.read(buf).map_err(|_| ())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<Size, ()> {
+        self.0.write_all(buf).map_err(|_| ())?;
+        self.0.flush().map_err(|_| ())?;
+        Ok(buf.len())
+    }
+
+    fn stat(&self) -> Result<FileStat, ()> {
+        let meta = self.0.metadata().map_err(|_| ())?;
+
+        Ok(FileStat {
+            size: meta.len(),
+            created: meta.created().map(timespec_of).unwrap_or_default(),
+            accessed: meta.accessed().map(timespec_of).unwrap_or_default(),
+            modified: meta.modified().map(timespec_of).unwrap_or_default(),
+        })
+    }
+}
+
+fn timespec_of(t: SystemTime) -> Timespec {
+    match t.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+        Err(_) => (0, 0),
+    }
+}
+
+fn get_file_mut(fd: Fd) -> &'static mut dyn VfsFile {
+    FTABLE.with(|ft| {
+        let mut ft = ft.lock().expect("require lock failed.");
+        if let Some(Some(file)) = ft.get_mut(fd as usize) {
+            let file_addr = file.as_mut() as *mut dyn VfsFile;
+            unsafe { &mut *file_addr }
+        } else {
+            panic!("fd don't exist");
+        }
+    })
+}
+
+#[no_mangle]
+pub fn hostfs_open(p: &str, flags: OpenFlags) -> Result<Fd, ()> {
+    let wants_write = flags.intersects(
+        OpenFlags::O_WRONLY
+            | OpenFlags::O_RDWR
+            | OpenFlags::O_CREAT
+            | OpenFlags::O_APPEND
+            | OpenFlags::O_TRUNC,
+    );
+    if wants_write && !SHARED_DIR.with(|(_, writable)| *writable) {
+        return Err(());
+    }
+
+    let real = resolve_guest_path(p)?;
+
+    // std rejects `truncate(true)`/`append(true)` with `write(false)`
+    // (`InvalidInput`), so `write` must cover every flag that implies
+    // writing, not just O_WRONLY/O_RDWR — otherwise a guest opening with
+    // just O_TRUNC or O_APPEND gets a spurious failure even on a
+    // writable mount. `wants_write` already covers exactly that set.
+    let mut options = fs::File::options();
+    options
+        .read(true)
+        .write(wants_write)
+        .create(flags.contains(OpenFlags::O_CREAT))
+        .append(flags.contains(OpenFlags::O_APPEND))
+        .truncate(flags.contains(OpenFlags::O_TRUNC));
+
+    let file = options.open(real).map_err(|_| ())?;
+
+    let fd = FTABLE.with(|table| {
+        let mut table = table.lock().expect("require lock failed.");
+        let handle: Box<dyn VfsFile> = Box::new(HostFile(file));
+        table.push(Some(handle));
+        table.len() - 1
+    });
+
+    Ok(fd as u32)
+}
+
+#[no_mangle]
+pub fn hostfs_read(fd: Fd, buf: &mut [u8]) -> Result<Size, ()> {
+    get_file_mut(fd).read(buf)
+}
+
+#[no_mangle]
+pub fn hostfs_write(fd: Fd, buf: &[u8]) -> Result<Size, ()> {
+    get_file_mut(fd).write(buf)
+}
+
+#[no_mangle]
+pub fn hostfs_close(fd: Fd) -> Result<(), ()> {
+    let mut old_file = None;
+
+    FTABLE.with(|ftable| {
+        let mut ftable = ftable.lock().expect("require lock failed.");
+        if (fd as usize) < ftable.len() {
+            std::mem::swap(&mut ftable[fd as usize], &mut old_file)
+        };
+    });
+
+    if let Some(file) = old_file {
+        file.close()
+    } else {
+        Err(())
+    }
+}
+
+#[no_mangle]
+pub fn hostfs_stat(fd: Fd) -> Result<FileStat, ()> {
+    FTABLE.with(|ft| {
+        let ft = ft.lock().expect("require lock failed.");
+        ft.get(fd as usize)
+            .and_then(|slot| slot.as_ref())
+            .ok_or(())?
+            .stat()
+    })
+}
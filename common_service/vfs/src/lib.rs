@@ -0,0 +1,27 @@
+#![allow(clippy::result_unit_err)]
+
+use ms_hostcall::types::{FileStat, OpenFlags, Size};
+
+/// An open file handle from any pluggable filesystem backend.
+///
+/// `fatfs_open`/`fatfs_read`/`fatfs_write`/`fatfs_close`/`fatfs_stat`
+/// dispatch through this trait instead of a hardwired `fatfs::File`, so a
+/// `Box<dyn VfsFile>` fd table can hold handles from mixed backends.
+pub trait VfsFile: Send {
+    fn read(&mut self, buf: &mut [u8]) -> Result<Size, ()>;
+    fn write(&mut self, buf: &[u8]) -> Result<Size, ()>;
+    fn stat(&self) -> Result<FileStat, ()>;
+    /// Release any backend-side resources held for this handle. Most
+    /// backends can rely on `Drop` instead; override when teardown needs
+    /// to be fallible (e.g. flushing buffered metadata).
+    fn close(self: Box<Self>) -> Result<(), ()> {
+        Ok(())
+    }
+}
+
+/// A mounted filesystem, selected per-isolation via `IsolationConfig`'s
+/// `fs_type` tag (e.g. `"fatfs"`, `"ext2"`).
+pub trait VfsBackend: Send {
+    fn open(&self, path: &str, flags: OpenFlags) -> Result<Box<dyn VfsFile>, ()>;
+    fn readdir(&self, path: &str) -> Result<Vec<String>, ()>;
+}
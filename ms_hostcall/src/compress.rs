@@ -0,0 +1,216 @@
+//! A `no_std` Snappy-style LZ77 block codec, used to shrink large `T`
+//! payloads before they cross the host/isolation boundary through the
+//! buffer subsystem.
+//!
+//! Stream layout: a varint-encoded uncompressed length, followed by
+//! elements each led by a tag byte. The low 2 bits of the tag pick the
+//! element kind: `0` is a literal run, `1`/`2`/`3` are back-reference
+//! copies carrying a 1-, 2-, or 4-byte offset.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+const MIN_MATCH: usize = 4;
+const MAX_COPY_LEN: usize = 64;
+
+fn put_varint(out: &mut Vec<u8>, mut n: usize) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn get_varint(data: &[u8]) -> Option<(usize, usize)> {
+    let mut result = 0usize;
+    let mut shift = 0;
+
+    for (i, &b) in data.iter().enumerate() {
+        result |= ((b & 0x7f) as usize) << shift;
+        if b & 0x80 == 0 {
+            return Some((result, i + 1));
+        }
+        shift += 7;
+    }
+
+    None
+}
+
+fn hash4(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).wrapping_mul(2654435761)
+}
+
+fn push_literal(out: &mut Vec<u8>, data: &[u8]) {
+    if data.is_empty() {
+        return;
+    }
+
+    if data.len() <= 60 {
+        out.push(((data.len() - 1) as u8) << 2);
+    } else {
+        let n = (data.len() - 1) as u64;
+        let mut nbytes = 1;
+        while (n >> (8 * nbytes)) != 0 {
+            nbytes += 1;
+        }
+
+        out.push(((59 + nbytes) as u8) << 2);
+        for k in 0..nbytes {
+            out.push((n >> (8 * k)) as u8);
+        }
+    }
+
+    out.extend_from_slice(data);
+}
+
+fn push_copy(out: &mut Vec<u8>, offset: usize, len: usize) {
+    let (elem_type, width) = if offset <= 0xff {
+        (1u8, 1)
+    } else if offset <= 0xffff {
+        (2u8, 2)
+    } else {
+        (3u8, 4)
+    };
+
+    let mut remaining = len;
+    while remaining > 0 {
+        let chunk = remaining.min(MAX_COPY_LEN);
+        out.push((((chunk - 1) as u8) << 2) | elem_type);
+
+        let off_bytes = (offset as u64).to_le_bytes();
+        out.extend_from_slice(&off_bytes[..width]);
+
+        remaining -= chunk;
+    }
+}
+
+/// Compress `input` into a self-describing block.
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2 + 16);
+    put_varint(&mut out, input.len());
+
+    let mut table: BTreeMap<u32, usize> = BTreeMap::new();
+    let mut literal_start = 0usize;
+    let mut i = 0usize;
+
+    while i + MIN_MATCH <= input.len() {
+        let h = hash4(&input[i..i + MIN_MATCH]);
+        let candidate = table.insert(h, i);
+
+        if let Some(cand) = candidate {
+            let offset = i - cand;
+            if offset > 0 && input[cand..cand + MIN_MATCH] == input[i..i + MIN_MATCH] {
+                let mut len = MIN_MATCH;
+                while i + len < input.len() && input[cand + len] == input[i + len] {
+                    len += 1;
+                }
+
+                push_literal(&mut out, &input[literal_start..i]);
+                push_copy(&mut out, offset, len);
+
+                i += len;
+                literal_start = i;
+                continue;
+            }
+        }
+
+        i += 1;
+    }
+
+    push_literal(&mut out, &input[literal_start..]);
+
+    out
+}
+
+/// Decompress a block produced by [`compress`].
+pub fn decompress(input: &[u8]) -> Result<Vec<u8>, ()> {
+    let (uncompressed_len, mut pos) = get_varint(input).ok_or(())?;
+    let mut out = Vec::with_capacity(uncompressed_len);
+
+    while out.len() < uncompressed_len {
+        let tag = *input.get(pos).ok_or(())?;
+        pos += 1;
+
+        let elem_type = tag & 0x3;
+        let value = (tag >> 2) as usize;
+
+        if elem_type == 0 {
+            let len = if value < 60 {
+                value + 1
+            } else {
+                let nbytes = value - 59;
+                let mut n = 0usize;
+                for k in 0..nbytes {
+                    n |= (*input.get(pos + k).ok_or(())? as usize) << (8 * k);
+                }
+                pos += nbytes;
+                n + 1
+            };
+
+            let end = pos + len;
+            out.extend_from_slice(input.get(pos..end).ok_or(())?);
+            pos = end;
+        } else {
+            let width = match elem_type {
+                1 => 1,
+                2 => 2,
+                3 => 4,
+                _ => unreachable!(),
+            };
+
+            let mut offset = 0usize;
+            for k in 0..width {
+                offset |= (*input.get(pos + k).ok_or(())? as usize) << (8 * k);
+            }
+            pos += width;
+
+            let len = value + 1;
+            if offset == 0 || offset > out.len() {
+                return Err(());
+            }
+
+            let start = out.len() - offset;
+            for k in 0..len {
+                let byte = out[start + k];
+                out.push(byte);
+            }
+        }
+    }
+
+    if out.len() != uncompressed_len {
+        return Err(());
+    }
+
+    Ok(out)
+}
+
+#[test]
+fn roundtrip_empty() {
+    assert_eq!(decompress(&compress(&[])).unwrap(), Vec::<u8>::new());
+}
+
+#[test]
+fn roundtrip_literal_only() {
+    let data = b"hello, isolation boundary!".to_vec();
+    assert_eq!(decompress(&compress(&data)).unwrap(), data);
+}
+
+#[test]
+fn roundtrip_repetitive() {
+    let mut data = Vec::new();
+    for _ in 0..200 {
+        data.extend_from_slice(b"abcdefgh");
+    }
+    let compressed = compress(&data);
+    assert!(compressed.len() < data.len());
+    assert_eq!(decompress(&compressed).unwrap(), data);
+}
+
+#[test]
+fn roundtrip_long_literal_run() {
+    let data: Vec<u8> = (0..500).map(|i| (i % 251) as u8).collect();
+    assert_eq!(decompress(&compress(&data)).unwrap(), data);
+}
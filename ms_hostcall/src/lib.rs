@@ -4,6 +4,7 @@
 
 extern crate alloc;
 
+pub mod compress;
 pub mod err;
 pub mod types;
 
@@ -19,6 +20,8 @@ pub enum CommonHostCall {
     Metric,
     #[display(fmt = "fs_image")]
     FsImage,
+    #[display(fmt = "shared_dir")]
+    SharedDir,
 
     #[display(fmt = "write")]
     Write,
@@ -48,6 +51,21 @@ pub enum CommonHostCall {
     FatfsRead,
     #[display(fmt = "fatfs_close")]
     FatfsClose,
+    #[display(fmt = "fatfs_stat")]
+    FatfsStat,
+    #[display(fmt = "fatfs_readdir")]
+    FatfsReaddir,
+
+    #[display(fmt = "hostfs_open")]
+    HostfsOpen,
+    #[display(fmt = "hostfs_write")]
+    HostfsWrite,
+    #[display(fmt = "hostfs_read")]
+    HostfsRead,
+    #[display(fmt = "hostfs_close")]
+    HostfsClose,
+    #[display(fmt = "hostfs_stat")]
+    HostfsStat,
 
     #[display(fmt = "addrinfo")]
     SmoltcpAddrInfo,
@@ -70,9 +88,25 @@ pub enum CommonHostCall {
     AccessBuffer,
     #[display(fmt = "buffer_dealloc")]
     BufferDealloc,
+    #[display(fmt = "buffer_alloc_compressed")]
+    BufferAllocCompressed,
+    #[display(fmt = "access_buffer_compressed")]
+    AccessBufferCompressed,
+    #[display(fmt = "buffer_dealloc_compressed")]
+    BufferDeallocCompressed,
 
     #[display(fmt = "get_time")]
     GetTime,
+
+    #[display(fmt = "get_random")]
+    GetRandom,
+
+    #[display(fmt = "poll_create")]
+    PollCreate,
+    #[display(fmt = "poll_ctl")]
+    PollCtl,
+    #[display(fmt = "poll_wait")]
+    PollWait,
 }
 
 #[derive(Debug, Display)]
@@ -85,7 +119,9 @@ impl HostCallID {
     pub fn belong_to(&self) -> ServiceName {
         match self {
             Self::Common(common) => match common {
-                CommonHostCall::Metric | CommonHostCall::FsImage => "".to_owned(),
+                CommonHostCall::Metric | CommonHostCall::FsImage | CommonHostCall::SharedDir => {
+                    "".to_owned()
+                }
 
                 CommonHostCall::Write
                 | CommonHostCall::Open
@@ -101,7 +137,15 @@ impl HostCallID {
                 CommonHostCall::FatfsOpen
                 | CommonHostCall::FatfsWrite
                 | CommonHostCall::FatfsRead
-                | CommonHostCall::FatfsClose => "fatfs".to_owned(),
+                | CommonHostCall::FatfsClose
+                | CommonHostCall::FatfsStat
+                | CommonHostCall::FatfsReaddir => "fatfs".to_owned(),
+
+                CommonHostCall::HostfsOpen
+                | CommonHostCall::HostfsWrite
+                | CommonHostCall::HostfsRead
+                | CommonHostCall::HostfsClose
+                | CommonHostCall::HostfsStat => "hostfs".to_owned(),
 
                 CommonHostCall::SmoltcpAddrInfo
                 | CommonHostCall::SmoltcpConnect
@@ -113,9 +157,18 @@ impl HostCallID {
 
                 CommonHostCall::BufferAlloc
                 | CommonHostCall::AccessBuffer
-                | CommonHostCall::BufferDealloc => "buffer".to_owned(),
+                | CommonHostCall::BufferDealloc
+                | CommonHostCall::BufferAllocCompressed
+                | CommonHostCall::AccessBufferCompressed
+                | CommonHostCall::BufferDeallocCompressed => "buffer".to_owned(),
 
                 CommonHostCall::GetTime => "time".to_owned(),
+
+                CommonHostCall::GetRandom => "random".to_owned(),
+
+                CommonHostCall::PollCreate
+                | CommonHostCall::PollCtl
+                | CommonHostCall::PollWait => "poll".to_owned(),
             },
             HostCallID::Custom(_) => todo!(),
         }
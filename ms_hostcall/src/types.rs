@@ -0,0 +1,70 @@
+use alloc::string::String;
+
+use bitflags::bitflags;
+
+pub type IsolationID = u32;
+pub type ServiceName = String;
+
+pub type Fd = u32;
+pub type Size = usize;
+
+bitflags! {
+    #[derive(Default)]
+    pub struct OpenFlags: u32 {
+        const O_RDONLY = 0;
+        const O_WRONLY = 1 << 0;
+        const O_RDWR   = 1 << 1;
+        const O_CREAT  = 1 << 2;
+        const O_APPEND = 1 << 3;
+        const O_TRUNC  = 1 << 4;
+    }
+}
+
+/// A single `(seconds, nanoseconds)` timestamp pair, following the
+/// `st_mtime`/`st_mtime_nsec`-style split POSIX platforms use for
+/// sub-second precision.
+pub type Timespec = (i64, u32);
+
+/// Metadata for an open file, returned by the `*_stat` hostcalls.
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct FileStat {
+    pub size: u64,
+    pub created: Timespec,
+    pub accessed: Timespec,
+    pub modified: Timespec,
+}
+
+pub type EpollFd = u32;
+
+bitflags! {
+    /// Readiness bits an epoll-style caller registers interest in, mirrored
+    /// back in [`Event::interest`] to report what actually fired.
+    #[derive(Default)]
+    pub struct Interest: u32 {
+        const READABLE       = 1 << 0;
+        const WRITABLE       = 1 << 1;
+        const CLOSED         = 1 << 2;
+        /// Re-arm after every readiness change instead of firing again on
+        /// every `poll_wait` while the condition still holds.
+        const EDGE_TRIGGERED = 1 << 3;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct Event {
+    pub fd: Fd,
+    pub interest: Interest,
+}
+
+#[test]
+fn interest_bits_are_distinct_and_combine() {
+    let both = Interest::READABLE | Interest::WRITABLE;
+
+    assert!(both.contains(Interest::READABLE));
+    assert!(both.contains(Interest::WRITABLE));
+    assert!(!both.contains(Interest::CLOSED));
+    assert!(!both.contains(Interest::EDGE_TRIGGERED));
+    assert_eq!(Interest::empty().bits(), 0);
+}
@@ -7,11 +7,23 @@ use crate::{libos::libos, println};
 
 pub type FaaSFuncResult<T> = Result<DataBuffer<T>, ()>;
 
+/// Where a `DataBuffer`'s backing memory came from, which decides how
+/// `Drop` reclaims it.
+#[derive(Debug)]
+enum Origin {
+    /// Lives in host-shared memory; release it through a hostcall.
+    HostBuffer,
+    /// A private, guest-side allocation (e.g. a decompressed copy); drop
+    /// it like any other boxed value.
+    Local,
+}
+
 #[derive(Debug)]
 pub struct DataBuffer<T> {
     inner: ManuallyDrop<Box<T>>,
 
     used: bool,
+    origin: Origin,
 }
 
 impl<T> DataBuffer<T>
@@ -42,6 +54,32 @@ where
         Self {
             inner: ManuallyDrop::new(inner),
             used: false,
+            origin: Origin::HostBuffer,
+        }
+    }
+
+    /// Like [`Self::with_slot`], but marks the slot for the host to
+    /// compress on drop/publish instead of shipping the raw bytes of `T`
+    /// across the isolation boundary.
+    pub fn with_slot_compressed(slot: String) -> Self
+    where
+        T: Default,
+    {
+        let p = {
+            let l: Layout = Layout::new::<T>();
+            let fingerprint = T::__fingerprint();
+
+            libos!(buffer_alloc_compressed(&slot, l, fingerprint)).expect("alloc failed.")
+                as *mut T
+        };
+
+        unsafe { core::ptr::write(p, T::default()) };
+        let inner = unsafe { Box::from_raw(p) };
+
+        Self {
+            inner: ManuallyDrop::new(inner),
+            used: false,
+            origin: Origin::HostBuffer,
         }
     }
 
@@ -63,6 +101,47 @@ where
             Self {
                 inner: ManuallyDrop::new(inner),
                 used: true,
+                origin: Origin::HostBuffer,
+            }
+        })
+    }
+
+    /// Like [`Self::from_buffer_slot`], but transparently decompresses
+    /// the block the host produced for a [`Self::with_slot_compressed`]
+    /// buffer before handing back a plain `T`.
+    pub fn from_buffer_slot_compressed(slot: String) -> Option<Self> {
+        let buffer_meta: Option<(usize, usize, u64)> = libos!(access_buffer_compressed(&slot));
+
+        buffer_meta.map(|(raw_ptr, compressed_len, fingerprint)| {
+            if fingerprint != T::__fingerprint() {
+                println!("wrong data type, {}, {}", fingerprint, T::__fingerprint());
+                panic!("");
+            };
+
+            let compressed =
+                unsafe { core::slice::from_raw_parts(raw_ptr as *const u8, compressed_len) };
+            let decompressed =
+                ms_hostcall::compress::decompress(compressed).expect("decompress failed.");
+
+            // The compressed bytes only live in the host's shared buffer
+            // for the duration of this decompress; release them now
+            // instead of leaking them in the 4 GiB service heap.
+            libos!(buffer_dealloc_compressed(raw_ptr, compressed_len));
+
+            let p = unsafe { alloc::alloc::alloc(Layout::new::<T>()) as *mut T };
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    decompressed.as_ptr(),
+                    p as *mut u8,
+                    core::mem::size_of::<T>(),
+                )
+            };
+            let inner = unsafe { Box::from_raw(p) };
+
+            Self {
+                inner: ManuallyDrop::new(inner),
+                used: true,
+                origin: Origin::Local,
             }
         })
     }
@@ -113,7 +192,12 @@ impl<T> Drop for DataBuffer<T> {
         if self.used {
             let ptr = Box::into_raw(unsafe { ManuallyDrop::take(&mut self.inner) });
             // println!("drop DataBuffer val: 0x{:x}", ptr as usize);
-            libos!(buffer_dealloc(ptr as usize, Layout::new::<T>()));
+            match self.origin {
+                Origin::HostBuffer => {
+                    libos!(buffer_dealloc(ptr as usize, Layout::new::<T>()));
+                }
+                Origin::Local => drop(unsafe { Box::from_raw(ptr) }),
+            }
         }
     }
 }
@@ -0,0 +1,46 @@
+use alloc::{string::String, vec::Vec};
+
+use ms_hostcall::types::{Fd, FileStat};
+
+use crate::libos::libos;
+
+/// Fetch metadata for an already-open file without consuming its handle,
+/// so it can be paired with the [`crate::io::Read`] calls on the same `fd`.
+pub fn metadata(fd: Fd) -> Result<FileStat, ()> {
+    libos!(fatfs_stat(fd))
+}
+
+/// List the entry names of a directory. `path` of `""` lists the mount's
+/// root.
+pub fn readdir(path: &str) -> Result<Vec<String>, ()> {
+    libos!(fatfs_readdir(path))
+}
+
+/// Guest-facing API for the `hostfs` passthrough backend: open, read,
+/// write, close and `metadata` on an fd. `metadata` has the same shape as
+/// the fatfs one above; there is no hostfs `readdir`.
+pub mod hostfs {
+    use ms_hostcall::types::{Fd, FileStat, OpenFlags, Size};
+
+    use crate::libos::libos;
+
+    pub fn open(path: &str, flags: OpenFlags) -> Result<Fd, ()> {
+        libos!(hostfs_open(path, flags))
+    }
+
+    pub fn read(fd: Fd, buf: &mut [u8]) -> Result<Size, ()> {
+        libos!(hostfs_read(fd, buf))
+    }
+
+    pub fn write(fd: Fd, buf: &[u8]) -> Result<Size, ()> {
+        libos!(hostfs_write(fd, buf))
+    }
+
+    pub fn close(fd: Fd) -> Result<(), ()> {
+        libos!(hostfs_close(fd))
+    }
+
+    pub fn metadata(fd: Fd) -> Result<FileStat, ()> {
+        libos!(hostfs_stat(fd))
+    }
+}
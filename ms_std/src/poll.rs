@@ -0,0 +1,48 @@
+use alloc::vec::Vec;
+
+use ms_hostcall::types::{EpollFd, Event, Fd, Interest};
+
+use crate::libos::libos;
+
+/// The default number of events a single `poll_wait` call can report;
+/// callers with busier fd sets should loop rather than grow this.
+const MAX_EVENTS: usize = 64;
+
+/// An epoll-style readiness notifier over smoltcp sockets and `fdtab` fds.
+pub struct Poller {
+    epfd: EpollFd,
+}
+
+impl Poller {
+    pub fn new() -> Self {
+        let epfd: EpollFd = libos!(poll_create()).expect("poll_create failed.");
+        Self { epfd }
+    }
+
+    /// Register or update interest in `fd`. Pass `Interest::EDGE_TRIGGERED`
+    /// to only be notified on readiness changes rather than every wait
+    /// while the condition still holds.
+    pub fn ctl(&self, fd: Fd, interest: Interest) -> Result<(), ()> {
+        libos!(poll_ctl(self.epfd, fd, interest))
+    }
+
+    pub fn deregister(&self, fd: Fd) -> Result<(), ()> {
+        libos!(poll_ctl(self.epfd, fd, Interest::empty()))
+    }
+
+    /// Block until at least one registered fd is ready, or `timeout_ms`
+    /// elapses, returning an iterator over the events that fired.
+    pub fn wait(&self, timeout_ms: u32) -> Result<impl Iterator<Item = Event>, ()> {
+        let mut events: Vec<Event> = alloc::vec![Event::default(); MAX_EVENTS];
+        let n: usize = libos!(poll_wait(self.epfd, &mut events, timeout_ms))?;
+
+        events.truncate(n);
+        Ok(events.into_iter())
+    }
+}
+
+impl Default for Poller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
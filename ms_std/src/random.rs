@@ -0,0 +1,22 @@
+use crate::libos::libos;
+
+/// Fill `buf` with bytes drawn from the host's OS entropy source.
+///
+/// The host either fills the whole buffer or fails outright, so there is
+/// no partial-fill case to special-case here; it is also safe to call
+/// before any heap allocation has happened in the isolation.
+pub fn fill_bytes(buf: &mut [u8]) -> Result<(), ()> {
+    libos!(get_random(buf))
+}
+
+pub fn u64() -> u64 {
+    let mut buf = [0u8; 8];
+    fill_bytes(&mut buf).expect("get_random failed.");
+    u64::from_ne_bytes(buf)
+}
+
+pub fn u32() -> u32 {
+    let mut buf = [0u8; 4];
+    fill_bytes(&mut buf).expect("get_random failed.");
+    u32::from_ne_bytes(buf)
+}
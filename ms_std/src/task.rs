@@ -0,0 +1,169 @@
+use alloc::{boxed::Box, collections::BTreeMap, string::String};
+use core::any::Any;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use ms_hostcall::types::IsolationID;
+
+/// A single FaaS invocation. Carries the call's `args` and backs the
+/// per-task slots declared with [`task_local!`], which are reset between
+/// invocations so a warm isolation thread can't leak state across
+/// tenants.
+pub struct Task {
+    pub args: BTreeMap<String, String>,
+}
+
+/// A bare spinlock around `T`.
+///
+/// `ms_std` is `no_std`, so `std::thread_local!`/`std::sync::Mutex` aren't
+/// available here; this is the smallest primitive that lets
+/// [`TASK_LOCALS`] be a plain `static` (sidestepping the `static_mut_refs`
+/// lint) while still serializing access across isolation threads.
+struct SpinLock<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    const fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+
+        let result = f(unsafe { &mut *self.value.get() });
+        self.locked.store(false, Ordering::Release);
+        result
+    }
+}
+
+// Keyed by `IsolationID` rather than a single shared map: `main.rs` runs
+// every isolation on its own OS thread, so a process-global map leaked
+// task-local state across tenants (the exact invariant `task_local!` is
+// supposed to prevent) and raced whenever two isolations touched it at
+// once. Each isolation only ever reads/writes its own entry.
+static TASK_LOCALS: SpinLock<BTreeMap<IsolationID, BTreeMap<String, Box<dyn Any>>>> =
+    SpinLock::new(BTreeMap::new());
+
+fn current_isolation() -> IsolationID {
+    crate::init_context::isolation_ctx().isol_id
+}
+
+fn clear_current() {
+    let isol_id = current_isolation();
+    TASK_LOCALS.with(|locals| {
+        locals.remove(&isol_id);
+    });
+}
+
+#[doc(hidden)]
+pub fn with_slot<T, I, F, R>(key: &str, init: I, f: F) -> R
+where
+    T: 'static,
+    I: FnOnce() -> T,
+    F: FnOnce(&T) -> R,
+{
+    let isol_id = current_isolation();
+
+    // Only touch the map (and hold TASK_LOCALS' spinlock) long enough to
+    // find-or-insert the slot, then drop the lock before calling `f`. `f`
+    // is arbitrary guest code that may itself re-enter `task_local!` —
+    // including the very same slot — and the spinlock isn't reentrant, so
+    // holding it across `f` would deadlock the isolation thread the
+    // moment that happened.
+    //
+    // Safe: the value lives in a `Box` this isolation's own map entry
+    // owns, so its address doesn't move even as the outer `BTreeMap`
+    // rebalances on inserts/removes from other isolations. Only
+    // `clear_current()` for *this* `isol_id` can invalidate it, and that
+    // only ever runs from `spawn_invocation`, before/after `main`, never
+    // while a `task_local!` access is in flight.
+    let value_ptr: *const T = TASK_LOCALS.with(|locals| {
+        let map = locals.entry(isol_id).or_insert_with(BTreeMap::new);
+        if !map.contains_key(key) {
+            map.insert(String::from(key), Box::new(init()));
+        }
+
+        map.get(key)
+            .and_then(|v| v.downcast_ref::<T>())
+            .expect("task_local type mismatch") as *const T
+    });
+
+    f(unsafe { &*value_ptr })
+}
+
+/// Declare a per-task slot, analogous to `std::thread_local!` but scoped
+/// to a single invocation instead of a thread.
+#[macro_export]
+macro_rules! task_local {
+    ($vis:vis static $name:ident: $ty:ty = $init:expr;) => {
+        #[allow(non_camel_case_types)]
+        $vis struct $name;
+
+        #[allow(non_upper_case_globals)]
+        $vis static $name: $name = $name;
+
+        impl $name {
+            pub fn with<F, R>(&self, f: F) -> R
+            where
+                F: FnOnce(&$ty) -> R,
+            {
+                $crate::task::with_slot(
+                    core::concat!(core::module_path!(), "::", core::stringify!($name)),
+                    || $init,
+                    f,
+                )
+            }
+        }
+    };
+}
+
+/// Run one FaaS invocation: clear every `task_local!` slot left over from
+/// the previous invocation on this (possibly warm) isolation thread, then
+/// call `main` with the fresh [`Task`].
+///
+/// `ms_std` is `no_std`, so `std::panic::catch_unwind` isn't available
+/// here, and this function sets up no unwind boundary of its own — if
+/// `main` panics, control unwinds straight past both the trailing
+/// `clear_current()` below and the `panic_handler` call on the error
+/// path; neither runs. The only guarantee this function actually provides
+/// is the *leading* `clear_current()`: whatever state a previous
+/// invocation left behind (panicked or not) is gone before the next one
+/// starts. Catching the panic itself, running `panic_handler` for it, and
+/// deciding whether to respawn the thread or keep reusing it is
+/// `Isolation::run`'s job in `libmsvisor`, which isn't part of this
+/// crate. The `Err` path below only covers `main` returning `Err(())`
+/// normally — not a panic — and exists so the handler still observes an
+/// ordinary task failure even when nothing unwound.
+pub fn spawn_invocation<F, T>(args: BTreeMap<String, String>, main: F) -> Result<T, ()>
+where
+    F: FnOnce(&Task) -> Result<T, ()>,
+{
+    clear_current();
+
+    let task = Task { args };
+    let result = main(&task);
+
+    if result.is_err() {
+        let handler = crate::init_context::isolation_ctx().panic_handler;
+        if handler != 0 {
+            let handler: extern "C" fn() = unsafe { core::mem::transmute(handler) };
+            handler();
+        }
+    }
+
+    clear_current();
+    result
+}